@@ -0,0 +1,181 @@
+use crate::json::Json;
+use std::error::Error;
+use std::fmt::Display;
+
+/// A value that can be rendered into a [`Json`] tree.
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+/// A value that can be reconstructed from a [`Json`] tree.
+///
+/// Failures carry a human-readable reason; the RPC layer maps any
+/// `FromJsonError` onto the `-32602` "Invalid method parameter" response.
+pub trait FromJson: Sized {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct FromJsonError {
+    pub reason: String,
+}
+
+impl FromJsonError {
+    fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    fn expected(what: &str, got: &Json) -> Self {
+        Self::new(format!("expected {}, found {}", what, got.stringify()))
+    }
+}
+
+impl Display for FromJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot decode from JSON: {}", self.reason)
+    }
+}
+
+impl Error for FromJsonError {}
+
+impl ToJson for Json {
+    fn to_json(&self) -> Json {
+        self.clone()
+    }
+}
+
+impl ToJson for f64 {
+    fn to_json(&self) -> Json {
+        Json::F64(*self)
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Json {
+        Json::Bool(*self)
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Json {
+        Json::String(self.to_string())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        match self {
+            Some(v) => v.to_json(),
+            None => Json::Null,
+        }
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError> {
+        match j {
+            Json::I64(n) => Ok(*n as f64),
+            Json::U64(n) => Ok(*n as f64),
+            Json::F64(n) => Ok(*n),
+            _ => Err(FromJsonError::expected("a number", j)),
+        }
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError> {
+        match j {
+            Json::Bool(b) => Ok(*b),
+            _ => Err(FromJsonError::expected("a boolean", j)),
+        }
+    }
+}
+
+impl FromJson for String {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError> {
+        match j {
+            Json::String(s) => Ok(s.clone()),
+            _ => Err(FromJsonError::expected("a string", j)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError> {
+        match j {
+            Json::Array(arr) => arr.iter().map(T::from_json).collect(),
+            _ => Err(FromJsonError::expected("an array", j)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError> {
+        match j {
+            Json::Null => Ok(None),
+            _ => T::from_json(j).map(Some),
+        }
+    }
+}
+
+impl<A: FromJson, B: FromJson> FromJson for (A, B) {
+    fn from_json(j: &Json) -> Result<Self, FromJsonError> {
+        match j {
+            Json::Array(arr) if arr.len() == 2 => Ok((A::from_json(&arr[0])?, B::from_json(&arr[1])?)),
+            Json::Array(arr) => Err(FromJsonError::new(format!(
+                "expected an array of length 2, found length {}",
+                arr.len()
+            ))),
+            _ => Err(FromJsonError::expected("an array", j)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(f64::from_json(&1.5_f64.to_json()).unwrap(), 1.5);
+        assert_eq!(bool::from_json(&true.to_json()).unwrap(), true);
+        assert_eq!(
+            String::from_json(&"abc".to_string().to_json()).unwrap(),
+            "abc"
+        );
+        let v = vec![1.0, 2.0, 3.0];
+        assert_eq!(Vec::<f64>::from_json(&v.to_json()).unwrap(), v);
+        let o: Option<f64> = Some(2.0);
+        assert_eq!(Option::<f64>::from_json(&o.to_json()).unwrap(), o);
+        let n: Option<f64> = None;
+        assert_eq!(Option::<f64>::from_json(&n.to_json()).unwrap(), n);
+    }
+
+    #[test]
+    fn test_tuple() {
+        let j = Json::Array(vec![Json::F64(3.0), Json::F64(4.0)]);
+        assert_eq!(<(f64, f64)>::from_json(&j).unwrap(), (3.0, 4.0));
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        assert!(f64::from_json(&Json::Bool(true)).is_err());
+        assert!(String::from_json(&Json::Null).is_err());
+        assert!(Vec::<f64>::from_json(&Json::F64(1.0)).is_err());
+        assert!(<(f64, f64)>::from_json(&Json::Array(vec![Json::F64(1.0)])).is_err());
+    }
+}
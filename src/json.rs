@@ -24,7 +24,9 @@ fn escape(s: &str) -> String {
 pub enum Json {
     Null,
     Bool(bool),
-    Number(f64),
+    I64(i64),
+    U64(u64),
+    F64(f64),
     String(String),
     Array(Vec<Json>),
     Object(Vec<(String, Json)>),
@@ -35,7 +37,9 @@ impl Json {
         match self {
             Json::Null => "null".to_string(),
             Json::Bool(b) => b.to_string(),
-            Json::Number(n) => n.to_string(),
+            Json::I64(n) => n.to_string(),
+            Json::U64(n) => n.to_string(),
+            Json::F64(n) => n.to_string(),
             Json::String(s) => escape(s),
             Json::Array(arr) => {
                 let arr = arr
@@ -56,6 +60,53 @@ impl Json {
         }
     }
 
+    /// Render the value across multiple lines with `indent`-space nesting and
+    /// a space after each `:`. Empty arrays and objects stay on a single line.
+    pub fn stringify_pretty(&self, indent: usize) -> String {
+        let mut buf = Vec::new();
+        self.write_pretty(&mut buf, indent).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// Stream the pretty-printed form directly into a writer, e.g. the RPC
+    /// response `writer`.
+    pub fn write_pretty(&self, w: &mut dyn std::io::Write, indent: usize) -> std::io::Result<()> {
+        self.write_pretty_at(w, indent, 0)
+    }
+
+    fn write_pretty_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        indent: usize,
+        level: usize,
+    ) -> std::io::Result<()> {
+        match self {
+            Json::Array(arr) if !arr.is_empty() => {
+                writeln!(w, "[")?;
+                for (i, v) in arr.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(w, ",")?;
+                    }
+                    write!(w, "{:pad$}", "", pad = (level + 1) * indent)?;
+                    v.write_pretty_at(w, indent, level + 1)?;
+                }
+                write!(w, "\n{:pad$}]", "", pad = level * indent)
+            }
+            Json::Object(obj) if !obj.is_empty() => {
+                writeln!(w, "{{")?;
+                for (i, (k, v)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(w, ",")?;
+                    }
+                    write!(w, "{:pad$}\"{}\": ", "", k, pad = (level + 1) * indent)?;
+                    v.write_pretty_at(w, indent, level + 1)?;
+                }
+                write!(w, "\n{:pad$}}}", "", pad = level * indent)
+            }
+            _ => write!(w, "{}", self.stringify()),
+        }
+    }
+
     pub fn parse_with_trailing_whitespace(s: &str) -> Result<Json> {
         let mut parser = Parser::new(s);
         parser.skip_whitespace();
@@ -78,9 +129,11 @@ where
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParseError {
     pub pos: usize,
+    pub line: usize,
+    pub col: usize,
     pub reason: String,
 }
 
@@ -88,7 +141,11 @@ type Result<T> = std::result::Result<T, ParseError>;
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "JSON Parse Error at {}: {}", self.pos, self.reason)
+        write!(
+            f,
+            "JSON Parse Error at line {} col {} (byte {}): {}",
+            self.line, self.col, self.pos, self.reason
+        )
     }
 }
 
@@ -108,24 +165,79 @@ fn deescape(c: u8) -> u8 {
     }
 }
 
-fn push_utf16(v: &mut Vec<u8>, iter: impl IntoIterator<Item = u16>) -> Result<()> {
+// Decode UTF-16 code units into `v`, returning only a reason on failure so
+// the caller can attach the real source position.
+fn push_utf16(
+    v: &mut Vec<u8>,
+    iter: impl IntoIterator<Item = u16>,
+) -> std::result::Result<(), String> {
     for c in char::decode_utf16(iter) {
         match c {
             Ok(c) => v.extend(c.encode_utf8(&mut [0; 4]).bytes()),
-            Err(_e) => {
-                return Err(ParseError {
-                    pos: 0,
-                    reason: format!("decode UTF-16 failed"),
-                })
-            }
+            Err(_e) => return Err("decode UTF-16 failed".to_string()),
         }
     }
     Ok(())
 }
 
+/// A single token emitted by [`StreamParser`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsonEvent {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    Key(String),
+    Null,
+    Boolean(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Error(ParseError),
+}
+
+// One entry of the parser state stack, tracking where we are inside a
+// still-open container so nesting is handled without recursion.
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    InArray { first: bool },
+    InObjectExpectKey { first: bool },
+    InObjectExpectValue,
+}
+
+/// An incremental JSON parser that yields a flat sequence of [`JsonEvent`]
+/// tokens instead of building a [`Json`] tree. `Key` events precede each
+/// object value, and a terminal `Error` event is emitted on malformed input
+/// rather than panicking.
+pub struct StreamParser<'a> {
+    parser: Parser<'a>,
+}
+
+impl<'a> StreamParser<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self {
+            parser: Parser::new(s),
+        }
+    }
+}
+
+impl Iterator for StreamParser<'_> {
+    type Item = JsonEvent;
+
+    fn next(&mut self) -> Option<JsonEvent> {
+        self.parser.next_event()
+    }
+}
+
 struct Parser<'a> {
     s: &'a str,
     i: usize,
+    stack: Vec<Frame>,
+    // whether the top-level value has already been started
+    started: bool,
+    // whether a terminal Error event has been emitted
+    errored: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -138,21 +250,184 @@ impl<'a> Parser<'a> {
     }
 
     pub fn error<T>(&self, reason: String) -> Result<T> {
-        Err(ParseError {
+        Err(self.make_error(reason))
+    }
+
+    fn make_error(&self, reason: String) -> ParseError {
+        let (line, col) = self.line_col(self.i);
+        ParseError {
             pos: self.i,
+            line,
+            col,
             reason,
-        })
+        }
     }
 
-    pub fn error_unexpected<T>(&self) -> Result<T> {
+    // Resolve a byte position to a 1-based line and column, advancing the line
+    // and resetting the column at each `\n`.
+    fn line_col(&self, pos: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for &b in &self.s.as_bytes()[..pos] {
+            if b == b'\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn unexpected_error(&self) -> ParseError {
         match self.cur() {
-            Some(c) => self.error(format!("unexpected char {:?}", c as char)),
-            None => self.error("unexpected EOF".into()),
+            Some(c) => self.make_error(format!("unexpected char {:?}", c as char)),
+            None => self.make_error("unexpected EOF".into()),
         }
     }
 
+    pub fn error_unexpected<T>(&self) -> Result<T> {
+        Err(self.unexpected_error())
+    }
+
     pub fn new(s: &'a str) -> Self {
-        Self { s, i: 0 }
+        Self {
+            s,
+            i: 0,
+            stack: Vec::new(),
+            started: false,
+            errored: false,
+        }
+    }
+
+    // Yield the next streaming event, or `None` once the document is finished
+    // or a terminal error has been reported.
+    fn next_event(&mut self) -> Option<JsonEvent> {
+        if self.errored {
+            return None;
+        }
+        let ev = match self.stack.last().copied() {
+            None => {
+                if self.started {
+                    return None;
+                }
+                self.started = true;
+                self.skip_whitespace();
+                self.cur()?;
+                self.read_value()
+            }
+            Some(Frame::InArray { first }) => self.next_in_array(first),
+            Some(Frame::InObjectExpectKey { first }) => self.next_in_object(first),
+            Some(Frame::InObjectExpectValue) => {
+                *self.stack.last_mut().unwrap() = Frame::InObjectExpectKey { first: false };
+                self.skip_whitespace();
+                self.read_value()
+            }
+        };
+        if matches!(ev, JsonEvent::Error(_)) {
+            self.errored = true;
+        }
+        Some(ev)
+    }
+
+    fn next_in_array(&mut self, first: bool) -> JsonEvent {
+        self.skip_whitespace();
+        match self.cur() {
+            Some(b']') => {
+                self.i += 1;
+                self.stack.pop();
+                return JsonEvent::ArrayEnd;
+            }
+            None => return JsonEvent::Error(self.unexpected_error()),
+            _ => {}
+        }
+        if !first {
+            match self.cur() {
+                Some(b',') => {
+                    self.i += 1;
+                    self.skip_whitespace();
+                }
+                _ => return JsonEvent::Error(self.unexpected_error()),
+            }
+        }
+        if let Some(Frame::InArray { first }) = self.stack.last_mut() {
+            *first = false;
+        }
+        self.read_value()
+    }
+
+    fn next_in_object(&mut self, first: bool) -> JsonEvent {
+        self.skip_whitespace();
+        match self.cur() {
+            Some(b'}') => {
+                self.i += 1;
+                self.stack.pop();
+                return JsonEvent::ObjectEnd;
+            }
+            None => return JsonEvent::Error(self.unexpected_error()),
+            _ => {}
+        }
+        if !first {
+            match self.cur() {
+                Some(b',') => {
+                    self.i += 1;
+                    self.skip_whitespace();
+                }
+                _ => return JsonEvent::Error(self.unexpected_error()),
+            }
+        }
+        if self.cur() != Some(b'"') {
+            return JsonEvent::Error(self.unexpected_error());
+        }
+        let key = match self.parse_string() {
+            Ok(k) => k,
+            Err(e) => return JsonEvent::Error(e),
+        };
+        self.skip_whitespace();
+        if self.cur() != Some(b':') {
+            return JsonEvent::Error(self.unexpected_error());
+        }
+        self.i += 1;
+        *self.stack.last_mut().unwrap() = Frame::InObjectExpectValue;
+        JsonEvent::Key(key)
+    }
+
+    // Read a single value at the current position, emitting its opening event
+    // and pushing a new frame for containers.
+    fn read_value(&mut self) -> JsonEvent {
+        match self.cur() {
+            Some(b'n') => match self.consume_identifier("null") {
+                Ok(()) => JsonEvent::Null,
+                Err(e) => JsonEvent::Error(e),
+            },
+            Some(b't') => match self.consume_identifier("true") {
+                Ok(()) => JsonEvent::Boolean(true),
+                Err(e) => JsonEvent::Error(e),
+            },
+            Some(b'f') => match self.consume_identifier("false") {
+                Ok(()) => JsonEvent::Boolean(false),
+                Err(e) => JsonEvent::Error(e),
+            },
+            Some(b'0'..=b'9' | b'-') => match self.parse_number() {
+                Ok(ev) => ev,
+                Err(e) => JsonEvent::Error(e),
+            },
+            Some(b'"') => match self.parse_string() {
+                Ok(s) => JsonEvent::String(s),
+                Err(e) => JsonEvent::Error(e),
+            },
+            Some(b'[') => {
+                self.i += 1;
+                self.stack.push(Frame::InArray { first: true });
+                JsonEvent::ArrayStart
+            }
+            Some(b'{') => {
+                self.i += 1;
+                self.stack.push(Frame::InObjectExpectKey { first: true });
+                JsonEvent::ObjectStart
+            }
+            _ => JsonEvent::Error(self.unexpected_error()),
+        }
     }
 
     fn skip_whitespace(&mut self) {
@@ -164,29 +439,70 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // Build a `Json` tree by consuming events off the streaming parser, keeping
+    // an explicit builder stack so the result matches the recursive grammar.
     pub fn parse_value(&mut self) -> Result<Json> {
-        match self.cur() {
-            Some(b'n') => self.parse_identifier("null", Json::Null),
-            Some(b't') => self.parse_identifier("true", Json::Bool(true)),
-            Some(b'f') => self.parse_identifier("false", Json::Bool(false)),
-            Some(b'0'..=b'9' | b'-') => self.parse_number(),
-            Some(b'"') => Ok(Json::String(self.parse_string()?)),
-            Some(b'[') => self.parse_array(),
-            Some(b'{') => self.parse_object(),
-            _ => self.error_unexpected(),
+        enum Build {
+            Array(Vec<Json>),
+            Object(Vec<(String, Json)>, Option<String>),
+        }
+        let mut builders: Vec<Build> = Vec::new();
+        loop {
+            let value = match self.next_event() {
+                Some(JsonEvent::Error(e)) => return Err(e),
+                Some(JsonEvent::ObjectStart) => {
+                    builders.push(Build::Object(Vec::new(), None));
+                    continue;
+                }
+                Some(JsonEvent::ArrayStart) => {
+                    builders.push(Build::Array(Vec::new()));
+                    continue;
+                }
+                Some(JsonEvent::Key(k)) => {
+                    if let Some(Build::Object(_, pending)) = builders.last_mut() {
+                        *pending = Some(k);
+                    }
+                    continue;
+                }
+                Some(JsonEvent::ObjectEnd) => match builders.pop() {
+                    Some(Build::Object(obj, _)) => Json::Object(obj),
+                    _ => unreachable!(),
+                },
+                Some(JsonEvent::ArrayEnd) => match builders.pop() {
+                    Some(Build::Array(arr)) => Json::Array(arr),
+                    _ => unreachable!(),
+                },
+                Some(JsonEvent::Null) => Json::Null,
+                Some(JsonEvent::Boolean(b)) => Json::Bool(b),
+                Some(JsonEvent::I64(n)) => Json::I64(n),
+                Some(JsonEvent::U64(n)) => Json::U64(n),
+                Some(JsonEvent::F64(n)) => Json::F64(n),
+                Some(JsonEvent::String(s)) => Json::String(s),
+                None => return self.error_unexpected(),
+            };
+            match builders.last_mut() {
+                None => return Ok(value),
+                Some(Build::Array(arr)) => arr.push(value),
+                Some(Build::Object(obj, pending)) => {
+                    let key = pending.take().unwrap();
+                    obj.push((key, value));
+                }
+            }
         }
     }
 
-    fn parse_identifier(&mut self, s: &str, val: Json) -> Result<Json> {
+    fn consume_identifier(&mut self, s: &str) -> Result<()> {
         if self.s[self.i..].starts_with(s) {
             self.i += s.len();
-            Ok(val)
+            Ok(())
         } else {
             self.error_unexpected()
         }
     }
 
-    fn parse_number(&mut self) -> Result<Json> {
+    // Consume a number literal, choosing the narrowest of `i64`/`u64`/`f64`
+    // that can hold an integer literal and falling back to `f64` otherwise.
+    fn parse_number(&mut self) -> Result<JsonEvent> {
         let start = self.i;
         while let Some(c) = self.cur() {
             if !b"0123456789.-+eE".contains(&c) {
@@ -195,9 +511,17 @@ impl<'a> Parser<'a> {
             self.i += 1;
         }
         let s = &self.s[start..self.i];
+        if !s.contains(['.', 'e', 'E']) {
+            if let Ok(n) = s.parse::<i64>() {
+                return Ok(JsonEvent::I64(n));
+            }
+            if let Ok(n) = s.parse::<u64>() {
+                return Ok(JsonEvent::U64(n));
+            }
+        }
         s.parse()
-            .map(Json::Number)
-            .or_else(|e| self.error(e.to_string()))
+            .map(JsonEvent::F64)
+            .or_else(|e: std::num::ParseFloatError| self.error(e.to_string()))
     }
 
     fn parse_string(&mut self) -> Result<String> {
@@ -226,7 +550,7 @@ impl<'a> Parser<'a> {
                                 };
                                 self.i += 4;
                                 if n < 0xD800 || n > 0xDFFF {
-                                    push_utf16(&mut v, [n]).or_else(|e| self.error(e.reason))?;
+                                    push_utf16(&mut v, [n]).or_else(|e| self.error(e))?;
                                 } else {
                                     // handle surrogate pair
                                     let Some("\\u") = self.ahead(2) else {
@@ -240,7 +564,7 @@ impl<'a> Parser<'a> {
                                         return self.error_unexpected();
                                     };
                                     self.i += 4;
-                                    push_utf16(&mut v, [n, n1])?;
+                                    push_utf16(&mut v, [n, n1]).or_else(|e| self.error(e))?;
                                 }
                             }
                             _ => return self.error_unexpected(),
@@ -255,64 +579,6 @@ impl<'a> Parser<'a> {
         }
         Ok(String::from_utf8(v).unwrap())
     }
-
-    // '[' ']' | '[' value (',' value)* ']'
-    fn parse_array(&mut self) -> Result<Json> {
-        let mut arr = Vec::new();
-        self.i += 1;
-        let mut first = true;
-        while let Some(_) = self.cur() {
-            self.skip_whitespace();
-            if let Some(b']') = self.cur() {
-                self.i += 1;
-                return Ok(Json::Array(arr));
-            }
-            if !first {
-                if let Some(b',') = self.cur() {
-                    self.i += 1;
-                } else {
-                    return self.error_unexpected();
-                }
-                self.skip_whitespace();
-            }
-            arr.push(self.parse_value()?);
-            first = false;
-        }
-        self.error_unexpected()
-    }
-
-    // '{' '}' | '{' key ':' value (',' key ':' value)* '}'
-    fn parse_object(&mut self) -> Result<Json> {
-        let mut obj = Vec::new();
-        self.i += 1;
-        let mut first = true;
-        while let Some(_) = self.cur() {
-            self.skip_whitespace();
-            if let Some(b'}') = self.cur() {
-                self.i += 1;
-                return Ok(Json::Object(obj));
-            }
-            if !first {
-                if let Some(b',') = self.cur() {
-                    self.i += 1;
-                } else {
-                    return self.error_unexpected();
-                }
-                self.skip_whitespace();
-            }
-            let key = self.parse_string()?;
-            self.skip_whitespace();
-            let Some(b':') = self.cur() else {
-                return self.error_unexpected();
-            };
-            self.i += 1;
-            self.skip_whitespace();
-            let val = self.parse_value()?;
-            obj.push((key, val));
-            first = false;
-        }
-        self.error_unexpected()
-    }
 }
 
 #[cfg(test)]
@@ -340,18 +606,19 @@ mod test {
         assert_stringify_eq(Null, "null");
         assert_stringify_eq(Bool(true), "true");
         assert_stringify_eq(Bool(false), "false");
-        assert_stringify_eq(Number(1.), "1");
-        assert_stringify_eq(Number(0.), "0");
-        assert_stringify_eq(Number(-1.), "-1");
-        assert_stringify_eq(Number(1.5), "1.5");
+        assert_stringify_eq(I64(1), "1");
+        assert_stringify_eq(I64(0), "0");
+        assert_stringify_eq(I64(-1), "-1");
+        assert_stringify_eq(F64(1.5), "1.5");
+        assert_stringify_eq(U64(u64::MAX), "18446744073709551615");
         assert_stringify_eq(String("abc".into()), "\"abc\"");
         assert_stringify_eq(Array(vec![]), "[]");
         assert_stringify_eq(Array(vec![Null, Bool(false)]), "[null,false]");
         assert_stringify_eq(Array(vec![Array(vec![]), Bool(false)]), "[[],false]");
         assert_stringify_eq(Object(vec![]), "{}");
-        assert_stringify_eq(Object(vec![("x".into(), Number(1.))]), "{\"x\":1}");
+        assert_stringify_eq(Object(vec![("x".into(), I64(1))]), "{\"x\":1}");
         assert_stringify_eq(
-            Object(vec![("x".into(), Number(1.)), ("y".into(), Null)]),
+            Object(vec![("x".into(), I64(1)), ("y".into(), Null)]),
             "{\"x\":1,\"y\":null}",
         );
     }
@@ -366,23 +633,99 @@ mod test {
         assert_parse_eq(Null, "null");
         assert_parse_eq(Bool(true), "true");
         assert_parse_eq(Bool(false), "false");
-        assert_parse_eq(Number(1.), "1");
-        assert_parse_eq(Number(0.), "0");
-        assert_parse_eq(Number(-1.), "-1");
-        assert_parse_eq(Number(1.5), "1.5");
+        assert_parse_eq(I64(1), "1");
+        assert_parse_eq(I64(0), "0");
+        assert_parse_eq(I64(-1), "-1");
+        assert_parse_eq(F64(1.5), "1.5");
         assert_parse_eq(String("abc".into()), "\"abc\"");
         assert_parse_eq(Array(vec![]), "[]");
         assert_parse_eq(Array(vec![Null, Bool(false)]), "[null,false]");
         assert_parse_eq(Array(vec![Array(vec![]), Bool(false)]), "[[],false]");
         assert_parse_eq(Object(vec![]), "{}");
-        assert_parse_eq(Object(vec![("x".into(), Number(1.))]), "{\"x\":1}");
+        assert_parse_eq(Object(vec![("x".into(), I64(1))]), "{\"x\":1}");
         assert_parse_eq(
-            Object(vec![("x".into(), Number(1.)), ("y".into(), Null)]),
+            Object(vec![("x".into(), I64(1)), ("y".into(), Null)]),
             "{\"x\":1,\"y\":null}",
         );
     }
 
+    #[test]
+    fn test_parse_number_variants() {
+        use Json::*;
+        // 64-bit ids that overflow f64 integer precision must round-trip exactly.
+        assert_parse_eq(I64(9007199254740993), "9007199254740993");
+        assert_parse_eq(I64(-9007199254740993), "-9007199254740993");
+        // values above i64::MAX fall back to u64, then to f64.
+        assert_parse_eq(U64(18446744073709551615), "18446744073709551615");
+        assert_parse_eq(F64(1e30), "1e30");
+        assert_parse_eq(F64(-2.5), "-2.5");
+    }
+
     fn assert_parse_eq(j: Json, s: &str) {
         assert_eq!(j, Json::parse_with_trailing_whitespace(s).unwrap());
     }
+
+    #[test]
+    fn test_stringify_pretty() {
+        use Json::*;
+        assert_eq!(Array(vec![]).stringify_pretty(2), "[]");
+        assert_eq!(Object(vec![]).stringify_pretty(2), "{}");
+        assert_eq!(
+            Object(vec![("x".into(), I64(1)), ("y".into(), Null)]).stringify_pretty(2),
+            "{\n  \"x\": 1,\n  \"y\": null\n}"
+        );
+        assert_eq!(
+            Object(vec![(
+                "a".into(),
+                Array(vec![I64(1), Object(vec![("b".into(), Bool(true))])])
+            )])
+            .stringify_pretty(2),
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": true\n    }\n  ]\n}"
+        );
+        assert_eq!(
+            Object(vec![("e".into(), Array(vec![]))]).stringify_pretty(2),
+            "{\n  \"e\": []\n}"
+        );
+    }
+
+    #[test]
+    fn test_stream_events() {
+        use JsonEvent::*;
+        let events: Vec<JsonEvent> =
+            StreamParser::new(r#"{"a":[1,{"b":null}],"c":true}"#).collect();
+        assert_eq!(
+            events,
+            vec![
+                ObjectStart,
+                Key("a".into()),
+                ArrayStart,
+                I64(1),
+                ObjectStart,
+                Key("b".into()),
+                Null,
+                ObjectEnd,
+                ArrayEnd,
+                Key("c".into()),
+                Boolean(true),
+                ObjectEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_line_col() {
+        let err = Json::parse_with_trailing_whitespace("[\n  1,\n  bad\n]").unwrap_err();
+        assert_eq!(err.pos, 9);
+        assert_eq!(err.line, 3);
+        assert_eq!(err.col, 3);
+        assert!(err
+            .to_string()
+            .starts_with("JSON Parse Error at line 3 col 3 (byte 9)"));
+    }
+
+    #[test]
+    fn test_stream_error_is_terminal() {
+        let events: Vec<JsonEvent> = StreamParser::new("[1,]").collect();
+        assert!(matches!(events.last(), Some(JsonEvent::Error(_))));
+    }
 }
@@ -0,0 +1,424 @@
+use crate::json::Json;
+use std::error::Error;
+use std::fmt::Display;
+
+/// A compiled JSONPath expression, evaluable against any [`Json`] tree.
+///
+/// Supports `$` root, `.name`/`['name']` child access, `*` wildcard, `[n]`
+/// indexing, `[start:end:step]` slicing (negative indices count from the end)
+/// and `..name` recursive descent.
+pub struct Path {
+    fragments: Vec<PathFragment>,
+}
+
+#[derive(Debug, Clone)]
+enum PathFragment {
+    Child(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Descendant(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct PathError {
+    pub reason: String,
+}
+
+impl PathError {
+    fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid JSONPath: {}", self.reason)
+    }
+}
+
+impl Error for PathError {}
+
+type Result<T> = std::result::Result<T, PathError>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dollar,
+    Dot,
+    DotDot,
+    Star,
+    LBracket,
+    RBracket,
+    Colon,
+    Name(String),
+    Quoted(String),
+    Int(i64),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '$' => {
+                chars.next();
+                tokens.push(Token::Dollar);
+            }
+            '.' => {
+                chars.next();
+                if chars.peek() == Some(&'.') {
+                    chars.next();
+                    tokens.push(Token::DotDot);
+                } else {
+                    tokens.push(Token::Dot);
+                }
+            }
+            '*' => {
+                chars.next();
+                tokens.push(Token::Star);
+            }
+            '[' => {
+                chars.next();
+                tokens.push(Token::LBracket);
+            }
+            ']' => {
+                chars.next();
+                tokens.push(Token::RBracket);
+            }
+            ':' => {
+                chars.next();
+                tokens.push(Token::Colon);
+            }
+            '\'' => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(PathError::new("unterminated quoted name")),
+                    }
+                }
+                tokens.push(Token::Quoted(name));
+            }
+            '-' | '0'..='9' => {
+                let mut num = String::new();
+                if c == '-' {
+                    num.push(c);
+                    chars.next();
+                }
+                while let Some(&d) = chars.peek() {
+                    if d.is_ascii_digit() {
+                        num.push(d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let n = num
+                    .parse()
+                    .map_err(|_| PathError::new(format!("invalid integer {:?}", num)))?;
+                tokens.push(Token::Int(n));
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut name = String::new();
+                while let Some(&d) = chars.peek() {
+                    if d == '.' || d == '[' || d == ']' || d.is_whitespace() {
+                        break;
+                    }
+                    name.push(d);
+                    chars.next();
+                }
+                tokens.push(Token::Name(name));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct FragmentParser {
+    tokens: Vec<Token>,
+    i: usize,
+}
+
+impl FragmentParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.i)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.i).cloned();
+        if t.is_some() {
+            self.i += 1;
+        }
+        t
+    }
+
+    fn expect(&mut self, t: Token) -> Result<()> {
+        if self.next().as_ref() == Some(&t) {
+            Ok(())
+        } else {
+            Err(PathError::new(format!("expected {:?}", t)))
+        }
+    }
+
+    fn optional_int(&mut self) -> Option<i64> {
+        if let Some(Token::Int(n)) = self.peek() {
+            let n = *n;
+            self.i += 1;
+            Some(n)
+        } else {
+            None
+        }
+    }
+
+    fn parse(&mut self) -> Result<Vec<PathFragment>> {
+        self.expect(Token::Dollar)?;
+        let mut fragments = Vec::new();
+        while let Some(t) = self.next() {
+            match t {
+                Token::Dot => match self.next() {
+                    Some(Token::Name(n)) => fragments.push(PathFragment::Child(n)),
+                    Some(Token::Star) => fragments.push(PathFragment::Wildcard),
+                    _ => return Err(PathError::new("expected a name after '.'")),
+                },
+                Token::DotDot => match self.next() {
+                    Some(Token::Name(n)) => fragments.push(PathFragment::Descendant(n)),
+                    _ => return Err(PathError::new("expected a name after '..'")),
+                },
+                Token::LBracket => fragments.push(self.parse_bracket()?),
+                _ => return Err(PathError::new(format!("unexpected token {:?}", t))),
+            }
+        }
+        Ok(fragments)
+    }
+
+    fn parse_bracket(&mut self) -> Result<PathFragment> {
+        let fragment = match self.peek() {
+            Some(Token::Quoted(_)) => {
+                let Some(Token::Quoted(name)) = self.next() else {
+                    unreachable!()
+                };
+                PathFragment::Child(name)
+            }
+            Some(Token::Star) => {
+                self.next();
+                PathFragment::Wildcard
+            }
+            _ => {
+                let first = self.optional_int();
+                if self.peek() == Some(&Token::Colon) {
+                    self.next();
+                    let second = self.optional_int();
+                    let third = if self.peek() == Some(&Token::Colon) {
+                        self.next();
+                        self.optional_int()
+                    } else {
+                        None
+                    };
+                    PathFragment::Slice(first, second, third)
+                } else {
+                    let idx =
+                        first.ok_or_else(|| PathError::new("expected an index inside '[]'"))?;
+                    PathFragment::Index(idx)
+                }
+            }
+        };
+        self.expect(Token::RBracket)?;
+        Ok(fragment)
+    }
+}
+
+fn resolve_index(len: usize, i: i64) -> Option<usize> {
+    let idx = if i < 0 { i + len as i64 } else { i };
+    if idx >= 0 && (idx as usize) < len {
+        Some(idx as usize)
+    } else {
+        None
+    }
+}
+
+fn collect_descendants<'a>(node: &'a Json, name: &str, out: &mut Vec<&'a Json>) {
+    match node {
+        Json::Object(obj) => {
+            for (k, v) in obj {
+                if k == name {
+                    out.push(v);
+                }
+                collect_descendants(v, name, out);
+            }
+        }
+        Json::Array(arr) => {
+            for v in arr {
+                collect_descendants(v, name, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Path {
+    pub fn compile(s: &str) -> Result<Path> {
+        let tokens = tokenize(s)?;
+        let mut parser = FragmentParser { tokens, i: 0 };
+        Ok(Path {
+            fragments: parser.parse()?,
+        })
+    }
+
+    pub fn query<'a>(&self, root: &'a Json) -> Vec<&'a Json> {
+        let mut set = vec![root];
+        for fragment in &self.fragments {
+            let mut next = Vec::new();
+            match fragment {
+                PathFragment::Child(name) => {
+                    for node in &set {
+                        if let Json::Object(obj) = node {
+                            for (k, v) in obj {
+                                if k == name {
+                                    next.push(v);
+                                }
+                            }
+                        }
+                    }
+                }
+                PathFragment::Wildcard => {
+                    for node in &set {
+                        match node {
+                            Json::Object(obj) => next.extend(obj.iter().map(|(_, v)| v)),
+                            Json::Array(arr) => next.extend(arr.iter()),
+                            _ => {}
+                        }
+                    }
+                }
+                PathFragment::Index(i) => {
+                    for node in &set {
+                        if let Json::Array(arr) = node {
+                            if let Some(idx) = resolve_index(arr.len(), *i) {
+                                next.push(&arr[idx]);
+                            }
+                        }
+                    }
+                }
+                PathFragment::Slice(start, end, step) => {
+                    for node in &set {
+                        if let Json::Array(arr) = node {
+                            slice_into(arr, *start, *end, *step, &mut next);
+                        }
+                    }
+                }
+                PathFragment::Descendant(name) => {
+                    for node in &set {
+                        collect_descendants(node, name, &mut next);
+                    }
+                }
+            }
+            set = next;
+        }
+        set
+    }
+}
+
+fn slice_into<'a>(
+    arr: &'a [Json],
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+    out: &mut Vec<&'a Json>,
+) {
+    let len = arr.len() as i64;
+    let norm = |x: i64| if x < 0 { x + len } else { x };
+    let step = step.unwrap_or(1);
+    if step == 0 {
+        return;
+    }
+    if step > 0 {
+        let start = start.map(norm).unwrap_or(0).clamp(0, len);
+        let end = end.map(norm).unwrap_or(len).clamp(0, len);
+        let mut i = start;
+        while i < end {
+            out.push(&arr[i as usize]);
+            i += step;
+        }
+    } else {
+        let start = start.map(norm).unwrap_or(len - 1).clamp(-1, len - 1);
+        let end = end.map(norm).unwrap_or(-1).clamp(-1, len - 1);
+        let mut i = start;
+        while i > end {
+            out.push(&arr[i as usize]);
+            i += step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn doc() -> Json {
+        Json::parse_with_trailing_whitespace(
+            r#"{"store":{"books":[{"title":"a"},{"title":"b"},{"title":"c"}],"name":"s"}}"#,
+        )
+        .unwrap()
+    }
+
+    fn titles(nodes: &[&Json]) -> Vec<Json> {
+        nodes.iter().map(|n| (*n).clone()).collect()
+    }
+
+    #[test]
+    fn test_child_and_index() {
+        let d = doc();
+        let nodes = Path::compile("$.store.books[0].title").unwrap().query(&d);
+        assert_eq!(titles(&nodes), vec![Json::String("a".into())]);
+    }
+
+    #[test]
+    fn test_negative_index() {
+        let d = doc();
+        let nodes = Path::compile("$.store.books[-1].title").unwrap().query(&d);
+        assert_eq!(titles(&nodes), vec![Json::String("c".into())]);
+    }
+
+    #[test]
+    fn test_slice() {
+        let d = doc();
+        let nodes = Path::compile("$.store.books[0:2]").unwrap().query(&d);
+        assert_eq!(nodes.len(), 2);
+        let stepped = Path::compile("$.store.books[::2]").unwrap().query(&d);
+        assert_eq!(stepped.len(), 2);
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let d = doc();
+        let nodes = Path::compile("$.store.books[*].title").unwrap().query(&d);
+        assert_eq!(
+            titles(&nodes),
+            vec![
+                Json::String("a".into()),
+                Json::String("b".into()),
+                Json::String("c".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_descent() {
+        let d = doc();
+        let nodes = Path::compile("$..title").unwrap().query(&d);
+        assert_eq!(nodes.len(), 3);
+    }
+
+    #[test]
+    fn test_bracket_name() {
+        let d = doc();
+        let nodes = Path::compile("$['store']['name']").unwrap().query(&d);
+        assert_eq!(titles(&nodes), vec![Json::String("s".into())]);
+    }
+}
@@ -2,9 +2,13 @@ use std::io::prelude::*;
 use std::io::BufReader;
 use std::net::{TcpListener, TcpStream};
 
+mod convert;
 mod json;
+mod jsonpath;
 
+use convert::{FromJson, ToJson};
 use json::Json;
+use jsonpath::Path;
 
 fn main() -> std::io::Result<()> {
     let listener = TcpListener::bind("127.0.0.1:7878")?;
@@ -48,7 +52,7 @@ fn response_error(
             (
                 "error".into(),
                 Json::Object(vec![
-                    ("code".into(), Json::Number(code as f64)),
+                    ("code".into(), Json::I64(code as i64)),
                     ("message".into(), message.into()),
                 ])
             ),
@@ -114,7 +118,7 @@ fn handle_single_json(writer: &mut dyn Write, kvs: &[(String, Json)]) -> std::io
         return Ok(true);
     };
     match id {
-        Json::Number(_) | Json::String(_) => (),
+        Json::I64(_) | Json::U64(_) | Json::F64(_) | Json::String(_) => (),
         Json::Null => return Ok(true),
         _ => return response_invalid_request(writer).map(|_| false),
     }
@@ -125,6 +129,7 @@ fn handle_single_json(writer: &mut dyn Write, kvs: &[(String, Json)]) -> std::io
     match method.as_str() {
         "add" => handle_method_add(writer, kvs, id).map(|_| false),
         "subtract" => handle_method_subtract(writer, kvs, id).map(|_| false),
+        "query" => handle_method_query(writer, kvs, id).map(|_| false),
         _ => response_error(writer, -32601, id.clone(), "Method not found").map(|_| false),
     }
 }
@@ -147,21 +152,45 @@ fn handle_method_add(
     kvs: &[(String, Json)],
     id: &Json,
 ) -> std::io::Result<()> {
-    let Some(Json::Array(args)) = get(&kvs, "params") else {
+    let Some(params) = get(&kvs, "params") else {
         return response_invalid_parameters(writer, id);
     };
-    let mut result = 0.0;
-    for arg in args {
-        if let Json::Number(x) = arg {
-            result += x;
-        } else {
-            return response_invalid_parameters(writer, id);
-        }
-    }
+    let Ok(args) = Vec::<f64>::from_json(params) else {
+        return response_invalid_parameters(writer, id);
+    };
+    let result: f64 = args.iter().sum();
+    let res = Json::Object(vec![
+        ("jsonrpc".into(), "2.0".into()),
+        ("id".into(), id.clone()),
+        ("result".into(), result.to_json()),
+    ])
+    .stringify();
+    write!(writer, "{res}")
+}
+
+fn handle_method_query(
+    writer: &mut dyn Write,
+    kvs: &[(String, Json)],
+    id: &Json,
+) -> std::io::Result<()> {
+    let Some(Json::Object(params)) = get(&kvs, "params") else {
+        return response_invalid_parameters(writer, id);
+    };
+    let Some(Json::String(path)) = get(params, "path") else {
+        return response_invalid_parameters(writer, id);
+    };
+    let Some(document) = get(params, "document") else {
+        return response_invalid_parameters(writer, id);
+    };
+    let Ok(path) = Path::compile(path) else {
+        return response_invalid_parameters(writer, id);
+    };
+    let nodes = path.query(document);
+    let result = Json::Array(nodes.into_iter().cloned().collect());
     let res = Json::Object(vec![
         ("jsonrpc".into(), "2.0".into()),
         ("id".into(), id.clone()),
-        ("result".into(), Json::Number(result)),
+        ("result".into(), result),
     ])
     .stringify();
     write!(writer, "{res}")
@@ -172,25 +201,17 @@ fn handle_method_subtract(
     kvs: &[(String, Json)],
     id: &Json,
 ) -> std::io::Result<()> {
-    let Some(Json::Array(args)) = get(&kvs, "params") else {
+    let Some(params) = get(&kvs, "params") else {
         return response_invalid_parameters(writer, id);
     };
-    if args.len() != 2 {
+    let Ok((minuend, subtrahend)) = <(f64, f64)>::from_json(params) else {
         return response_invalid_parameters(writer, id);
-    }
-    let mut arg_nums = [0.0; 2];
-    for (i, arg) in args.iter().enumerate() {
-        if let Json::Number(x) = arg {
-            arg_nums[i] = *x;
-        } else {
-            return response_invalid_parameters(writer, id);
-        }
-    }
-    let result = arg_nums[0] - arg_nums[1];
+    };
+    let result = minuend - subtrahend;
     let res = Json::Object(vec![
         ("jsonrpc".into(), "2.0".into()),
         ("id".into(), id.clone()),
-        ("result".into(), Json::Number(result)),
+        ("result".into(), result.to_json()),
     ])
     .stringify();
     write!(writer, "{res}")